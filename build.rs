@@ -0,0 +1,24 @@
+use std::env;
+use std::fs;
+use std::path::Path;
+
+fn main() {
+    let compiler = shaderc::Compiler::new().expect("Failed to initialize shader compiler");
+    compile_shader(&compiler, "src/rendering/shaders/scene.vert", shaderc::ShaderKind::Vertex);
+    compile_shader(&compiler, "src/rendering/shaders/scene.frag", shaderc::ShaderKind::Fragment);
+    compile_shader(&compiler, "src/rendering/shaders/overlay.vert", shaderc::ShaderKind::Vertex);
+    compile_shader(&compiler, "src/rendering/shaders/overlay.frag", shaderc::ShaderKind::Fragment);
+}
+
+fn compile_shader(compiler: &shaderc::Compiler, path: &str, kind: shaderc::ShaderKind) {
+    println!("cargo:rerun-if-changed={}", path);
+    let source = fs::read_to_string(path).expect("Failed to read shader source");
+    let binary = compiler
+        .compile_into_spirv(&source, kind, path, "main", None)
+        .expect("Failed to compile shader");
+
+    let out_dir = env::var("OUT_DIR").unwrap();
+    let file_name = format!("{}.spv", Path::new(path).file_name().unwrap().to_str().unwrap());
+    fs::write(Path::new(&out_dir).join(file_name), binary.as_binary_u8())
+        .expect("Failed to write compiled shader");
+}
@@ -0,0 +1,55 @@
+use nalgebra_glm as glm;
+
+/// An orbit camera: it always looks at `target` from a point `radius` away,
+/// positioned by `yaw`/`pitch` around it.
+pub struct Camera {
+    yaw: f32,
+    pitch: f32,
+    radius: f32,
+    target: glm::Vec3,
+}
+
+const MIN_PITCH: f32 = -1.5;
+const MAX_PITCH: f32 = 1.5;
+const MIN_RADIUS: f32 = 2.0;
+const MAX_RADIUS: f32 = 200.0;
+
+impl Camera {
+    pub fn new() -> Self {
+        Self {
+            yaw: 0.0,
+            pitch: 0.4,
+            radius: 25.0,
+            target: glm::vec3(0.0, 0.0, 0.0),
+        }
+    }
+
+    pub fn orbit(&mut self, delta_yaw: f32, delta_pitch: f32) {
+        self.yaw += delta_yaw;
+        self.pitch = (self.pitch + delta_pitch).clamp(MIN_PITCH, MAX_PITCH);
+    }
+
+    pub fn zoom(&mut self, delta: f32) {
+        self.radius = (self.radius - delta).clamp(MIN_RADIUS, MAX_RADIUS);
+    }
+
+    pub fn eye(&self) -> glm::Vec3 {
+        let horizontal = self.radius * self.pitch.cos();
+        self.target
+            + glm::vec3(
+                horizontal * self.yaw.sin(),
+                self.radius * self.pitch.sin(),
+                horizontal * self.yaw.cos(),
+            )
+    }
+
+    pub fn view_matrix(&self) -> glm::Mat4 {
+        glm::look_at(&self.eye(), &self.target, &glm::vec3(0.0, 1.0, 0.0))
+    }
+}
+
+impl Default for Camera {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -1,65 +1,140 @@
 mod content;
+mod overlay;
 mod rendering;
 
-use winit::{
-    event::{WindowEvent},
-    event_loop::EventLoop,
-    window::Window
-};
+use overlay::{Overlay, OverlayEvent, OverlayProducer};
+use std::time::Instant;
+use winit::event::WindowEvent;
 use winit::application::ApplicationHandler;
-use winit::event::{DeviceEvent, DeviceId, StartCause};
-use winit::event_loop::{ActiveEventLoop, ControlFlow};
-use winit::window::{WindowAttributes, WindowId};
+use winit::event::{DeviceEvent, DeviceId, ElementState, KeyEvent, MouseScrollDelta};
+use winit::event_loop::ActiveEventLoop;
+use winit::event_loop::{ControlFlow, EventLoop};
+use winit::keyboard::{KeyCode, PhysicalKey};
+use winit::window::WindowId;
+
+const ORBIT_SENSITIVITY: f32 = 0.005;
+const ZOOM_SENSITIVITY: f32 = 1.0;
+const OVERLAY_EVENT_CAPACITY: usize = 256;
 
 struct App {
     device: rendering::Device,
     view: Option<rendering::WindowView>,
+    camera: content::Camera,
+    overlay: Overlay,
+    overlay_producer: OverlayProducer,
+    frame_count: u32,
+    last_redraw: Instant,
 }
 
 impl App {
     fn new(event_loop: &EventLoop<()>) -> Self {
-        let device = rendering::Device::new(event_loop, vec![]).expect("Failed to setup Vulkan - Aborting!");
-        Self { device, view: None }
+        let device = rendering::Device::new(
+            event_loop,
+            rendering::ExtensionSet::new(),
+            rendering::ExtensionSet::new(),
+        )
+        .expect("Failed to setup Vulkan - Aborting!");
+        let (overlay, overlay_producer) = Overlay::new(OVERLAY_EVENT_CAPACITY);
+        Self {
+            device,
+            view: None,
+            camera: content::Camera::new(),
+            overlay,
+            overlay_producer,
+            frame_count: 0,
+            last_redraw: Instant::now(),
+        }
     }
-}
 
-impl ApplicationHandler for App {
-    fn new_events(&mut self, event_loop: &ActiveEventLoop, cause: StartCause) {
-        todo!()
+    fn redraw(&mut self) {
+        let Some(view) = &mut self.view else {
+            return;
+        };
+        if view.needs_resize() {
+            if let Err(err) = self.device.recreate_swapchain(view) {
+                eprintln!("Failed to recreate swapchain: {:?}", err);
+                return;
+            }
+        }
+
+        // Until a real decode/playback thread feeds the HUD, the render loop
+        // advances the frame counter and FPS readout itself.
+        self.frame_count += 1;
+        let now = Instant::now();
+        let dt = now.duration_since(self.last_redraw).as_secs_f32();
+        self.last_redraw = now;
+        self.overlay_producer
+            .push(OverlayEvent::Frame(self.frame_count));
+        if dt > 0.0 {
+            self.overlay_producer.push(OverlayEvent::Fps(1.0 / dt));
+        }
+
+        if let Err(err) = self
+            .device
+            .draw_frame(view, &self.camera, &mut self.overlay)
+        {
+            eprintln!("Failed to draw frame: {:?}", err);
+        }
     }
+}
 
+impl ApplicationHandler for App {
     fn resumed(&mut self, event_loop: &ActiveEventLoop) {
         if let Some(_) = &self.view { return; }
         let view = rendering::WindowView::new(event_loop, &mut self.device).expect("Failed to setup Vulkan - Aborting!");
         self.view = Some(view);
     }
 
-    fn user_event(&mut self, event_loop: &ActiveEventLoop, event: ()) {
-        todo!()
-    }
-
     fn window_event(&mut self, event_loop: &ActiveEventLoop, window_id: WindowId, event: WindowEvent) {
-        todo!()
-    }
-
-    fn device_event(&mut self, event_loop: &ActiveEventLoop, device_id: DeviceId, event: DeviceEvent) {
-        todo!()
-    }
-
-    fn about_to_wait(&mut self, event_loop: &ActiveEventLoop) {
-        todo!()
-    }
-
-    fn suspended(&mut self, event_loop: &ActiveEventLoop) {
-        todo!()
+        let Some(view) = &self.view else {
+            return;
+        };
+        if view.window().id() != window_id {
+            return;
+        }
+        match event {
+            WindowEvent::CloseRequested => event_loop.exit(),
+            WindowEvent::Resized(_) => {
+                if let Some(view) = &mut self.view {
+                    view.mark_needs_resize();
+                }
+            }
+            WindowEvent::RedrawRequested => self.redraw(),
+            WindowEvent::KeyboardInput {
+                event:
+                    KeyEvent {
+                        physical_key: PhysicalKey::Code(KeyCode::F1),
+                        state: ElementState::Pressed,
+                        repeat: false,
+                        ..
+                    },
+                ..
+            } => self.overlay.toggle_visible(),
+            _ => {}
+        }
     }
 
-    fn exiting(&mut self, event_loop: &ActiveEventLoop) {
-        todo!()
+    fn device_event(&mut self, _event_loop: &ActiveEventLoop, _device_id: DeviceId, event: DeviceEvent) {
+        match event {
+            DeviceEvent::MouseMotion { delta: (dx, dy) } => {
+                self.camera
+                    .orbit(dx as f32 * ORBIT_SENSITIVITY, dy as f32 * ORBIT_SENSITIVITY);
+            }
+            DeviceEvent::MouseWheel { delta } => {
+                let scroll = match delta {
+                    MouseScrollDelta::LineDelta(_, y) => y,
+                    MouseScrollDelta::PixelDelta(pos) => pos.y as f32 / 100.0,
+                };
+                self.camera.zoom(scroll * ZOOM_SENSITIVITY);
+            }
+            _ => {}
+        }
     }
 
-    fn memory_warning(&mut self, event_loop: &ActiveEventLoop) {
-        todo!()
+    fn about_to_wait(&mut self, _event_loop: &ActiveEventLoop) {
+        if let Some(view) = &self.view {
+            view.window().request_redraw();
+        }
     }
 }
 
@@ -0,0 +1,284 @@
+use ringbuf::traits::{Consumer as _, Producer as _, Split};
+use ringbuf::{HeapCons, HeapProd, HeapRb};
+use std::sync::RwLock;
+
+/// A single telemetry update pushed from playback/decode logic to the HUD.
+pub enum OverlayEvent {
+    Frame(u32),
+    PlaybackSpeed(f32),
+    Fps(f32),
+    Boost(f32),
+    Score { blue: u32, orange: u32 },
+}
+
+/// Latest HUD readouts, guarded independently of the ring buffer so the
+/// render thread can draw the last-known values while new events are still
+/// in flight.
+pub struct OverlayState {
+    pub visible: bool,
+    pub frame: u32,
+    pub playback_speed: f32,
+    pub fps: f32,
+    pub boost: f32,
+    pub score: (u32, u32),
+}
+
+impl OverlayState {
+    fn apply(&mut self, event: OverlayEvent) {
+        match event {
+            OverlayEvent::Frame(frame) => self.frame = frame,
+            OverlayEvent::PlaybackSpeed(speed) => self.playback_speed = speed,
+            OverlayEvent::Fps(fps) => self.fps = fps,
+            OverlayEvent::Boost(boost) => self.boost = boost,
+            OverlayEvent::Score { blue, orange } => self.score = (blue, orange),
+        }
+    }
+}
+
+impl Default for OverlayState {
+    fn default() -> Self {
+        Self {
+            visible: true,
+            frame: 0,
+            playback_speed: 1.0,
+            fps: 0.0,
+            boost: 0.0,
+            score: (0, 0),
+        }
+    }
+}
+
+/// A vertex of the HUD's glyph-quad mesh, in pixel space (the orthographic
+/// projection maps it onto the swapchain extent).
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct OverlayVertex {
+    pub pos: [f32; 2],
+    pub color: [f32; 4],
+}
+
+const TEXT_COLOR: [f32; 4] = [0.9, 0.95, 0.9, 0.9];
+const GLYPH_WIDTH: usize = 5;
+const GLYPH_HEIGHT: usize = 7;
+const GLYPH_SCALE: f32 = 3.0;
+const GLYPH_ADVANCE: f32 = (GLYPH_WIDTH + 1) as f32 * GLYPH_SCALE;
+const LINE_HEIGHT: f32 = (GLYPH_HEIGHT + 2) as f32 * GLYPH_SCALE;
+const MARGIN: f32 = 16.0;
+
+/// The HUD overlay: a consumer draining telemetry events pushed by
+/// [`OverlayProducer`], plus the last-known state used to build the glyph
+/// mesh each frame. `state` is behind its own lock so playback logic
+/// (pushing through the producer) never blocks on the render thread
+/// (draining and reading through [`Overlay::overlay`]).
+pub struct Overlay {
+    consumer: HeapCons<OverlayEvent>,
+    state: RwLock<OverlayState>,
+}
+
+/// The producer half, handed to whatever pushes telemetry - today that's
+/// the render loop itself, advancing the frame counter and FPS each redraw.
+pub struct OverlayProducer {
+    producer: HeapProd<OverlayEvent>,
+}
+
+impl Overlay {
+    pub fn new(capacity: usize) -> (Self, OverlayProducer) {
+        let (producer, consumer) = HeapRb::new(capacity).split();
+        (
+            Self {
+                consumer,
+                state: RwLock::new(OverlayState::default()),
+            },
+            OverlayProducer { producer },
+        )
+    }
+
+    pub fn overlay(&self) -> std::sync::RwLockReadGuard<'_, OverlayState> {
+        self.state.read().unwrap()
+    }
+
+    pub fn overlay_mut(&self) -> std::sync::RwLockWriteGuard<'_, OverlayState> {
+        self.state.write().unwrap()
+    }
+
+    pub fn toggle_visible(&self) {
+        let mut state = self.overlay_mut();
+        state.visible = !state.visible;
+    }
+
+    /// Drains every event queued since the last call, folding them into the
+    /// HUD's state. Called once per frame by the render thread.
+    pub fn drain(&mut self) {
+        let mut state = self.state.write().unwrap();
+        while let Some(event) = self.consumer.try_pop() {
+            state.apply(event);
+        }
+    }
+
+    /// Builds the glyph-quad mesh for the current state, anchored to the
+    /// top-left corner of a `width` x `height` viewport.
+    pub fn build_vertices(&self, width: f32, height: f32) -> Vec<OverlayVertex> {
+        let state = self.overlay();
+        if !state.visible {
+            return Vec::new();
+        }
+        let lines = [
+            format!("FRAME {}", state.frame),
+            format!("SPEED {:.2}X", state.playback_speed),
+            format!("FPS {:.1}", state.fps),
+            format!("BOOST {:.0}%", state.boost),
+            format!("SCORE {}-{}", state.score.0, state.score.1),
+        ];
+
+        let max_chars = ((width - MARGIN) / GLYPH_ADVANCE).floor().max(0.0) as usize;
+
+        let mut vertices = Vec::new();
+        for (row, line) in lines.iter().enumerate() {
+            let y = MARGIN + row as f32 * LINE_HEIGHT;
+            if y > height {
+                break;
+            }
+            let clipped: String = line.chars().take(max_chars).collect();
+            push_line(&mut vertices, &clipped, MARGIN, y);
+        }
+        vertices
+    }
+}
+
+impl OverlayProducer {
+    /// Pushes an event, silently dropping it if the consumer has fallen
+    /// behind and the ring buffer is full.
+    pub fn push(&mut self, event: OverlayEvent) {
+        let _ = self.producer.try_push(event);
+    }
+}
+
+fn push_line(vertices: &mut Vec<OverlayVertex>, line: &str, x: f32, y: f32) {
+    for (column, ch) in line.chars().enumerate() {
+        let glyph_x = x + column as f32 * GLYPH_ADVANCE;
+        push_glyph(vertices, ch, glyph_x, y);
+    }
+}
+
+fn push_glyph(vertices: &mut Vec<OverlayVertex>, ch: char, x: f32, y: f32) {
+    let Some(rows) = glyph_rows(ch) else {
+        return;
+    };
+    for (row, bits) in rows.iter().enumerate() {
+        for column in 0..GLYPH_WIDTH {
+            if bits & (1 << (GLYPH_WIDTH - 1 - column)) == 0 {
+                continue;
+            }
+            let px = x + column as f32 * GLYPH_SCALE;
+            let py = y + row as f32 * GLYPH_SCALE;
+            push_quad(vertices, px, py, GLYPH_SCALE, GLYPH_SCALE);
+        }
+    }
+}
+
+fn push_quad(vertices: &mut Vec<OverlayVertex>, x: f32, y: f32, w: f32, h: f32) {
+    let top_left = [x, y];
+    let top_right = [x + w, y];
+    let bottom_left = [x, y + h];
+    let bottom_right = [x + w, y + h];
+    for pos in [
+        top_left,
+        bottom_left,
+        top_right,
+        top_right,
+        bottom_left,
+        bottom_right,
+    ] {
+        vertices.push(OverlayVertex {
+            pos,
+            color: TEXT_COLOR,
+        });
+    }
+}
+
+/// A compact 5x7 bitmap font covering the digits and letters the HUD needs.
+/// Each row is packed MSB-first into the low `GLYPH_WIDTH` bits.
+fn glyph_rows(c: char) -> Option<[u8; GLYPH_HEIGHT]> {
+    Some(match c {
+        '0' => [
+            0b01110, 0b10001, 0b10011, 0b10101, 0b11001, 0b10001, 0b01110,
+        ],
+        '1' => [
+            0b00100, 0b01100, 0b00100, 0b00100, 0b00100, 0b00100, 0b01110,
+        ],
+        '2' => [
+            0b01110, 0b10001, 0b00001, 0b00010, 0b00100, 0b01000, 0b11111,
+        ],
+        '3' => [
+            0b11111, 0b00010, 0b00100, 0b00010, 0b00001, 0b10001, 0b01110,
+        ],
+        '4' => [
+            0b00010, 0b00110, 0b01010, 0b10010, 0b11111, 0b00010, 0b00010,
+        ],
+        '5' => [
+            0b11111, 0b10000, 0b11110, 0b00001, 0b00001, 0b10001, 0b01110,
+        ],
+        '6' => [
+            0b00110, 0b01000, 0b10000, 0b11110, 0b10001, 0b10001, 0b01110,
+        ],
+        '7' => [
+            0b11111, 0b00001, 0b00010, 0b00100, 0b01000, 0b01000, 0b01000,
+        ],
+        '8' => [
+            0b01110, 0b10001, 0b10001, 0b01110, 0b10001, 0b10001, 0b01110,
+        ],
+        '9' => [
+            0b01110, 0b10001, 0b10001, 0b01111, 0b00001, 0b00010, 0b01100,
+        ],
+        'A' => [
+            0b01110, 0b10001, 0b10001, 0b11111, 0b10001, 0b10001, 0b10001,
+        ],
+        'B' => [
+            0b11110, 0b10001, 0b10001, 0b11110, 0b10001, 0b10001, 0b11110,
+        ],
+        'C' => [
+            0b01110, 0b10001, 0b10000, 0b10000, 0b10000, 0b10001, 0b01110,
+        ],
+        'D' => [
+            0b11100, 0b10010, 0b10001, 0b10001, 0b10001, 0b10010, 0b11100,
+        ],
+        'E' => [
+            0b11111, 0b10000, 0b10000, 0b11110, 0b10000, 0b10000, 0b11111,
+        ],
+        'F' => [
+            0b11111, 0b10000, 0b10000, 0b11110, 0b10000, 0b10000, 0b10000,
+        ],
+        'M' => [
+            0b10001, 0b11011, 0b10101, 0b10101, 0b10001, 0b10001, 0b10001,
+        ],
+        'O' => [
+            0b01110, 0b10001, 0b10001, 0b10001, 0b10001, 0b10001, 0b01110,
+        ],
+        'P' => [
+            0b11110, 0b10001, 0b10001, 0b11110, 0b10000, 0b10000, 0b10000,
+        ],
+        'R' => [
+            0b11110, 0b10001, 0b10001, 0b11110, 0b10100, 0b10010, 0b10001,
+        ],
+        'S' => [
+            0b01111, 0b10000, 0b10000, 0b01110, 0b00001, 0b00001, 0b11110,
+        ],
+        'T' => [
+            0b11111, 0b00100, 0b00100, 0b00100, 0b00100, 0b00100, 0b00100,
+        ],
+        'X' => [
+            0b10001, 0b10001, 0b01010, 0b00100, 0b01010, 0b10001, 0b10001,
+        ],
+        '-' => [
+            0b00000, 0b00000, 0b00000, 0b11111, 0b00000, 0b00000, 0b00000,
+        ],
+        '.' => [
+            0b00000, 0b00000, 0b00000, 0b00000, 0b00000, 0b01100, 0b01100,
+        ],
+        '%' => [
+            0b11001, 0b11010, 0b00010, 0b00100, 0b01000, 0b01011, 0b10011,
+        ],
+        ' ' => [0; GLYPH_HEIGHT],
+        _ => return None,
+    })
+}
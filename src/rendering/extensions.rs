@@ -0,0 +1,165 @@
+use super::VulkanError;
+use ash::vk;
+use std::ffi::{CStr, CString};
+use std::os::raw::c_char;
+
+/// A set of Vulkan instance/device extension names. Owns its `CString`s so
+/// the pointers [`ExtensionSet::as_ptrs`] hands to a `*CreateInfo` stay
+/// valid for as long as this set is alive - in particular, for the
+/// duration of the `create_instance`/`create_device` call it's used for.
+#[derive(Default, Clone)]
+pub struct ExtensionSet {
+    names: Vec<CString>,
+}
+
+impl ExtensionSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Appends `name` to this set. Public so a caller can opt a particular
+    /// `Device::new` invocation into extensions beyond what this renderer
+    /// already requires; no caller in this binary does that yet, so this
+    /// sits unused outside the tests below.
+    #[allow(dead_code)]
+    pub fn push(&mut self, name: &str) -> Result<(), VulkanError> {
+        self.names.push(CString::new(name)?);
+        Ok(())
+    }
+
+    pub(super) fn push_cstr(&mut self, name: &CStr) {
+        self.names.push(name.to_owned());
+    }
+
+    pub(super) fn extend_from_ptrs(&mut self, names: &[*const c_char]) {
+        for &name in names {
+            self.push_cstr(unsafe { CStr::from_ptr(name) });
+        }
+    }
+
+    pub(super) fn as_ptrs(&self) -> Vec<*const c_char> {
+        self.names.iter().map(|name| name.as_ptr()).collect()
+    }
+
+    /// Checks every name in this set against `supported` (the result of
+    /// `enumerate_instance_extension_properties`/`enumerate_device_extension_properties`),
+    /// returning the first one that isn't there.
+    pub(super) fn validate(
+        &self,
+        supported: &[vk::ExtensionProperties],
+    ) -> Result<(), VulkanError> {
+        for name in &self.names {
+            let available = supported.iter().any(|props| {
+                (unsafe { CStr::from_ptr(props.extension_name.as_ptr()) }) == name.as_c_str()
+            });
+            if !available {
+                return Err(VulkanError::UnsupportedExtension(
+                    name.to_string_lossy().into_owned(),
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A set of Vulkan instance layer names, analogous to [`ExtensionSet`] but
+/// validated against `enumerate_instance_layer_properties`.
+#[derive(Default, Clone)]
+pub struct LayerSet {
+    names: Vec<CString>,
+}
+
+impl LayerSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&mut self, name: &str) -> Result<(), VulkanError> {
+        self.names.push(CString::new(name)?);
+        Ok(())
+    }
+
+    pub(super) fn as_ptrs(&self) -> Vec<*const c_char> {
+        self.names.iter().map(|name| name.as_ptr()).collect()
+    }
+
+    pub(super) fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    /// Returns `true` if every name in this set appears in `supported`
+    /// (the result of `enumerate_instance_layer_properties`).
+    pub(super) fn all_supported(&self, supported: &[vk::LayerProperties]) -> bool {
+        self.names.iter().all(|name| {
+            supported.iter().any(|props| {
+                (unsafe { CStr::from_ptr(props.layer_name.as_ptr()) }) == name.as_c_str()
+            })
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Copies `name` (plus a NUL terminator) into a fixed-size `c_char`
+    /// array the way the Vulkan loader fills `extension_name`/`layer_name`.
+    fn fixed_name(name: &str) -> [c_char; 256] {
+        let mut buf = [0 as c_char; 256];
+        for (dst, &src) in buf.iter_mut().zip(name.as_bytes()) {
+            *dst = src as c_char;
+        }
+        buf
+    }
+
+    fn fake_extension(name: &str) -> vk::ExtensionProperties {
+        vk::ExtensionProperties {
+            extension_name: fixed_name(name),
+            spec_version: 1,
+        }
+    }
+
+    fn fake_layer(name: &str) -> vk::LayerProperties {
+        vk::LayerProperties {
+            layer_name: fixed_name(name),
+            spec_version: 1,
+            implementation_version: 1,
+            description: fixed_name("test layer"),
+        }
+    }
+
+    #[test]
+    fn validate_passes_when_extension_is_supported() {
+        let mut set = ExtensionSet::new();
+        set.push("VK_KHR_surface").unwrap();
+        let supported = [fake_extension("VK_KHR_surface")];
+        assert!(set.validate(&supported).is_ok());
+    }
+
+    #[test]
+    fn validate_reports_missing_extension() {
+        let mut set = ExtensionSet::new();
+        set.push("VK_KHR_surface").unwrap();
+        let supported = [fake_extension("VK_KHR_win32_surface")];
+        match set.validate(&supported) {
+            Err(VulkanError::UnsupportedExtension(name)) => assert_eq!(name, "VK_KHR_surface"),
+            other => panic!("expected UnsupportedExtension, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn all_supported_is_true_when_every_layer_is_present() {
+        let mut set = LayerSet::new();
+        set.push("VK_LAYER_KHRONOS_validation").unwrap();
+        let supported = [fake_layer("VK_LAYER_KHRONOS_validation")];
+        assert!(set.all_supported(&supported));
+    }
+
+    #[test]
+    fn all_supported_is_false_when_a_layer_is_missing() {
+        let mut set = LayerSet::new();
+        set.push("VK_LAYER_KHRONOS_validation").unwrap();
+        let supported = [fake_layer("VK_LAYER_other")];
+        assert!(!set.all_supported(&supported));
+    }
+}
@@ -1,25 +1,72 @@
-use ash::khr::swapchain;
+use ash::khr::{surface, swapchain};
 use ash::vk::{PhysicalDevice, PhysicalDeviceType, SwapchainCreateInfoKHR, SwapchainKHR};
 use ash::{vk, Instance};
-use std::alloc;
-use std::alloc::{alloc, Layout};
+use std::collections::HashSet;
 use std::ffi::{CStr, CString, NulError};
 use std::os::raw::c_char;
-use std::str::FromStr;
 use winit::error::OsError;
 use winit::event_loop::{ActiveEventLoop, EventLoop};
 use winit::raw_window_handle::{HandleError, HasDisplayHandle, HasWindowHandle};
 use winit::window::{Window, WindowAttributes};
 
+mod extensions;
+pub use extensions::{ExtensionSet, LayerSet};
+
+use crate::content::Camera;
+use crate::overlay::OverlayVertex;
+use nalgebra_glm as glm;
+
+const MAX_FRAMES_IN_FLIGHT: usize = 2;
+const MAX_OVERLAY_VERTICES: usize = 4096;
+
+const SCENE_VERT_SPV: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/scene.vert.spv"));
+const SCENE_FRAG_SPV: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/scene.frag.spv"));
+const OVERLAY_VERT_SPV: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/overlay.vert.spv"));
+const OVERLAY_FRAG_SPV: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/overlay.frag.spv"));
+
+/// One host-visible buffer per frame-in-flight, plus its backing memory and
+/// the pointer it's permanently mapped at. Shared shape for the camera UBO
+/// and overlay vertex buffer, both allocated this way.
+type MappedBuffers = (
+    Vec<vk::Buffer>,
+    Vec<vk::DeviceMemory>,
+    Vec<*mut std::ffi::c_void>,
+);
+
+/// The per-frame-in-flight sync objects `create_sync_objects` allocates:
+/// image-available semaphores, render-finished semaphores and fences.
+type SyncObjects = (Vec<vk::Semaphore>, Vec<vk::Semaphore>, Vec<vk::Fence>);
+
+/// A fully-prepared overlay draw for the current frame: the pipeline plus
+/// the vertex range and projection to push for it. Built in [`Device::draw_frame`]
+/// from [`crate::overlay::Overlay`] and threaded into [`Device::record_command_buffer`].
+pub(crate) struct OverlayDraw {
+    pipeline: vk::Pipeline,
+    pipeline_layout: vk::PipelineLayout,
+    vertex_buffer: vk::Buffer,
+    vertex_count: u32,
+    projection: glm::Mat4,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+struct CameraUbo {
+    model: glm::Mat4,
+    view: glm::Mat4,
+    projection: glm::Mat4,
+}
+
 #[derive(Debug)]
 pub enum VulkanError {
     Loading(ash::LoadingError),
     Error(vk::Result),
     NoSuitableDevice,
+    NoSuitableMemoryType,
     UnableToFindQueueFamily,
     NulError(NulError),
     WindowHandleError(HandleError),
     OsError(OsError),
+    UnsupportedExtension(String),
 }
 
 impl From<ash::LoadingError> for VulkanError {
@@ -56,113 +103,261 @@ pub struct WindowView {
     window: Window,
     surface: vk::SurfaceKHR,
     swapchain: SwapchainKHR,
+    images: Vec<vk::Image>,
+    image_views: Vec<vk::ImageView>,
+    format: vk::Format,
+    extent: vk::Extent2D,
+    render_pass: vk::RenderPass,
+    descriptor_set_layout: vk::DescriptorSetLayout,
+    pipeline_layout: vk::PipelineLayout,
+    pipeline: vk::Pipeline,
+    framebuffers: Vec<vk::Framebuffer>,
+    command_pool: vk::CommandPool,
+    command_buffers: Vec<vk::CommandBuffer>,
+    descriptor_pool: vk::DescriptorPool,
+    descriptor_sets: Vec<vk::DescriptorSet>,
+    uniform_buffers: Vec<vk::Buffer>,
+    uniform_buffers_memory: Vec<vk::DeviceMemory>,
+    uniform_buffers_mapped: Vec<*mut std::ffi::c_void>,
+    overlay_pipeline_layout: vk::PipelineLayout,
+    overlay_pipeline: vk::Pipeline,
+    overlay_vertex_buffers: Vec<vk::Buffer>,
+    overlay_vertex_buffers_memory: Vec<vk::DeviceMemory>,
+    overlay_vertex_buffers_mapped: Vec<*mut std::ffi::c_void>,
+    image_available_semaphores: Vec<vk::Semaphore>,
+    render_finished_semaphores: Vec<vk::Semaphore>,
+    in_flight_fences: Vec<vk::Fence>,
+    images_in_flight: Vec<vk::Fence>,
+    current_frame: usize,
+    needs_resize: bool,
+    logical: ash::Device,
+    swapchain_loader: swapchain::Device,
+    surface_loader: surface::Instance,
+}
+
+struct DebugMessenger {
+    loader: ash::ext::debug_utils::Instance,
+    messenger: vk::DebugUtilsMessengerEXT,
 }
 
 pub struct Device {
     entry: ash::Entry,
     instance: Instance,
-    logical: ash::Device,
+    device_extensions: ExtensionSet,
     physical: PhysicalDevice,
-    swapchain_loader: swapchain::Device,
+    logical: Option<ash::Device>,
+    swapchain_loader: Option<swapchain::Device>,
+    graphics_queue_family: Option<u32>,
+    present_queue_family: Option<u32>,
+    graphics_queue: Option<vk::Queue>,
+    present_queue: Option<vk::Queue>,
+    debug_messenger: Option<DebugMessenger>,
 }
 
 impl WindowView {
-    pub fn new(event_loop: &ActiveEventLoop, device: &Device) -> Result<Self, VulkanError> {
+    pub fn new(event_loop: &ActiveEventLoop, device: &mut Device) -> Result<Self, VulkanError> {
         let attribs = WindowAttributes::default().with_title("RL Replay Viewer");
         let window = event_loop.create_window(attribs)?;
-        let (surface, swapchain) = device.link_to_window(&window)?;
+        let setup = device.link_to_window(&window)?;
+
+        let render_pass = device.create_render_pass(setup.format)?;
+        let descriptor_set_layout = device.create_descriptor_set_layout()?;
+        let (pipeline_layout, pipeline) =
+            device.create_pipeline(render_pass, descriptor_set_layout)?;
+        let framebuffers =
+            device.create_framebuffers(render_pass, &setup.image_views, setup.extent)?;
+        let command_pool = device.create_command_pool()?;
+        let command_buffers =
+            device.create_command_buffers(command_pool, framebuffers.len() as u32)?;
+        let (uniform_buffers, uniform_buffers_memory, uniform_buffers_mapped) =
+            device.create_uniform_buffers()?;
+        let descriptor_pool = device.create_descriptor_pool()?;
+        let descriptor_sets = device.create_descriptor_sets(
+            descriptor_pool,
+            descriptor_set_layout,
+            &uniform_buffers,
+        )?;
+        let (overlay_pipeline_layout, overlay_pipeline) =
+            device.create_overlay_pipeline(render_pass)?;
+        let (overlay_vertex_buffers, overlay_vertex_buffers_memory, overlay_vertex_buffers_mapped) =
+            device.create_overlay_vertex_buffers()?;
+        for (index, &command_buffer) in command_buffers.iter().enumerate() {
+            device.record_command_buffer(
+                command_buffer,
+                render_pass,
+                framebuffers[index],
+                pipeline,
+                pipeline_layout,
+                descriptor_sets[0],
+                None,
+                setup.extent,
+            )?;
+        }
+        let (image_available_semaphores, render_finished_semaphores, in_flight_fences) =
+            device.create_sync_objects()?;
+        let images_in_flight = vec![vk::Fence::null(); setup.images.len()];
+
         Ok(Self {
             window,
-            surface,
-            swapchain,
+            surface: setup.surface,
+            swapchain: setup.swapchain,
+            images: setup.images,
+            image_views: setup.image_views,
+            format: setup.format,
+            extent: setup.extent,
+            render_pass,
+            descriptor_set_layout,
+            pipeline_layout,
+            pipeline,
+            framebuffers,
+            command_pool,
+            command_buffers,
+            descriptor_pool,
+            descriptor_sets,
+            uniform_buffers,
+            uniform_buffers_memory,
+            uniform_buffers_mapped,
+            overlay_pipeline_layout,
+            overlay_pipeline,
+            overlay_vertex_buffers,
+            overlay_vertex_buffers_memory,
+            overlay_vertex_buffers_mapped,
+            image_available_semaphores,
+            render_finished_semaphores,
+            in_flight_fences,
+            images_in_flight,
+            current_frame: 0,
+            needs_resize: false,
+            logical: device.logical_handle(),
+            swapchain_loader: device.swapchain_loader_handle(),
+            surface_loader: device.surface_loader(),
         })
     }
+
+    pub fn window(&self) -> &Window {
+        &self.window
+    }
+
+    pub fn needs_resize(&self) -> bool {
+        self.needs_resize
+    }
+
+    pub fn mark_needs_resize(&mut self) {
+        self.needs_resize = true;
+    }
+}
+
+impl Drop for WindowView {
+    fn drop(&mut self) {
+        unsafe {
+            let _ = self.logical.device_wait_idle();
+            for &fence in &self.in_flight_fences {
+                self.logical.destroy_fence(fence, None);
+            }
+            for &semaphore in &self.render_finished_semaphores {
+                self.logical.destroy_semaphore(semaphore, None);
+            }
+            for &semaphore in &self.image_available_semaphores {
+                self.logical.destroy_semaphore(semaphore, None);
+            }
+            self.logical.destroy_command_pool(self.command_pool, None);
+            for &framebuffer in &self.framebuffers {
+                self.logical.destroy_framebuffer(framebuffer, None);
+            }
+            self.logical
+                .destroy_descriptor_pool(self.descriptor_pool, None);
+            for (&buffer, &memory) in self
+                .uniform_buffers
+                .iter()
+                .zip(&self.uniform_buffers_memory)
+            {
+                self.logical.unmap_memory(memory);
+                self.logical.destroy_buffer(buffer, None);
+                self.logical.free_memory(memory, None);
+            }
+            self.logical
+                .destroy_descriptor_set_layout(self.descriptor_set_layout, None);
+            for (&buffer, &memory) in self
+                .overlay_vertex_buffers
+                .iter()
+                .zip(&self.overlay_vertex_buffers_memory)
+            {
+                self.logical.unmap_memory(memory);
+                self.logical.destroy_buffer(buffer, None);
+                self.logical.free_memory(memory, None);
+            }
+            self.logical.destroy_pipeline(self.overlay_pipeline, None);
+            self.logical
+                .destroy_pipeline_layout(self.overlay_pipeline_layout, None);
+            self.logical.destroy_pipeline(self.pipeline, None);
+            self.logical
+                .destroy_pipeline_layout(self.pipeline_layout, None);
+            self.logical.destroy_render_pass(self.render_pass, None);
+            for &image_view in &self.image_views {
+                self.logical.destroy_image_view(image_view, None);
+            }
+            self.swapchain_loader.destroy_swapchain(self.swapchain, None);
+            self.surface_loader.destroy_surface(self.surface, None);
+        }
+    }
 }
 
 impl Device {
+    /// Sets up the Vulkan instance and picks a physical device. `instance_extensions`
+    /// and `device_extensions` are merged with the extensions this renderer
+    /// always needs (the window system's required extensions and `VK_KHR_swapchain`)
+    /// and validated against what the driver actually reports, so a typo'd or
+    /// unsupported name comes back as [`VulkanError::UnsupportedExtension`]
+    /// instead of a dangling pointer reaching `vkCreateInstance`/`vkCreateDevice`.
     pub fn new(
         event_loop: &EventLoop<()>,
-        extensions_instance: Vec<&str>,
-        extensions_device: Vec<&str>,
+        mut instance_extensions: ExtensionSet,
+        mut device_extensions: ExtensionSet,
     ) -> Result<Self, VulkanError> {
-        let (instance_ext, device_ext) = unsafe {
-            Self::efficiently_handle_extensions(event_loop, extensions_instance, extensions_device)?
-        };
+        let required =
+            ash_window::enumerate_required_extensions(event_loop.display_handle()?.as_raw())?;
+        instance_extensions.extend_from_ptrs(required);
+        device_extensions.push_cstr(swapchain::NAME);
+
+        let entry = unsafe { ash::Entry::load()? };
+        let available_instance_extensions =
+            unsafe { entry.enumerate_instance_extension_properties(None)? };
+        instance_extensions.validate(&available_instance_extensions)?;
+
+        let (instance, debug_messenger) = unsafe { Self::init(&entry, &instance_extensions)? };
+        let physical = unsafe { Self::pick_physical(&instance)? };
+
+        let available_device_extensions =
+            unsafe { instance.enumerate_device_extension_properties(physical)? };
+        device_extensions.validate(&available_device_extensions)?;
+
         #[cfg(debug_assertions)]
         unsafe {
-            let mut extensions: Vec<String> = instance_ext
-                .iter()
-                .map(|&c| CStr::from_ptr(c.clone()).to_string_lossy().to_string())
-                .collect();
-            println!("Instance Extensions: {:?}", extensions);
-            extensions = device_ext
-                .iter()
-                .map(|&c| CStr::from_ptr(c.clone()).to_string_lossy().to_string())
-                .collect();
-            println!("Device Extensions: {:?}", extensions);
-        }
-        let (entry, instance) = unsafe { Self::init(&instance_ext)? };
-        let physical = unsafe { Self::pick_physical(&instance)? };
-        let queue_family_index =
-            if let Some(u) = unsafe { Self::find_queue_family_index(&instance, physical) } {
-                u
-            } else {
-                return Err(VulkanError::UnableToFindQueueFamily);
+            let describe = |set: &ExtensionSet| -> Vec<String> {
+                set.as_ptrs()
+                    .iter()
+                    .map(|&c| CStr::from_ptr(c).to_string_lossy().to_string())
+                    .collect()
             };
-        let logical =
-            unsafe { Self::create_logical(&instance, physical, &device_ext, queue_family_index)? };
-        let swapchain_loader = swapchain::Device::new(&instance, &logical);
+            println!("Instance Extensions: {:?}", describe(&instance_extensions));
+            println!("Device Extensions: {:?}", describe(&device_extensions));
+        }
+
         Ok(Self {
             entry,
             instance,
-            logical,
+            device_extensions,
             physical,
-            swapchain_loader,
+            logical: None,
+            swapchain_loader: None,
+            graphics_queue_family: None,
+            present_queue_family: None,
+            graphics_queue: None,
+            present_queue: None,
+            debug_messenger,
         })
     }
 
-    #[inline]
-    unsafe fn efficiently_handle_extensions(
-        event_loop: &EventLoop<()>,
-        instance: Vec<&str>,
-        device: Vec<&str>,
-    ) -> Result<(Box<[*const c_char]>, Box<[*const c_char]>), VulkanError> {
-        let instance_required =
-            ash_window::enumerate_required_extensions(event_loop.display_handle()?.as_raw())?;
-        let device_required = &[swapchain::NAME.as_ptr()];
-        let calculate_length = |cs: &Vec<&str>, cp: &[*const c_char]| cs.len() + cp.len();
-        let l_inst_cp = calculate_length(&instance, instance_required);
-        let l_dev_cp = calculate_length(&device, device_required);
-        let total_len = l_inst_cp + l_dev_cp;
-        println!("total_len: {}", total_len);
-        let layout =
-            Layout::from_size_align(total_len * size_of::<&str>(), align_of::<*const c_char>())
-                .expect("Incorrect alignment");
-        let raw = alloc(layout) as *mut *const c_char;
-        let inst_cp = std::slice::from_raw_parts_mut(raw, l_inst_cp - 1);
-        let dev_cp = std::slice::from_raw_parts_mut(raw.add(l_inst_cp), l_dev_cp - 1);
-
-        let append = |dest: &mut [*const c_char], src1: Vec<&str>, src2: &[*const c_char]| {
-            let mut i = 0;
-            src1.iter().for_each(|&s| {
-                println!("{}", s);
-                dest[i] = CString::from_str(s)
-                    .expect("CString error")
-                    .as_c_str()
-                    .as_ptr();
-                i += 1;
-            });
-            // Can't implement the following loop using Rust Iterator's as there's no built-in size to the slice.
-            // Learnt the hard way and spent an hour and a half debugging the memory access violation.
-            while i < dest.len() {
-                dest[i] = src2[i - src1.len()];
-                i += 1;
-            }
-        };
-        append(inst_cp, instance, instance_required);
-        append(dev_cp, device, device_required);
-        Ok((Box::from_raw(inst_cp), Box::from_raw(dev_cp)))
-    }
     #[inline]
     unsafe fn pick_physical(instance: &Instance) -> Result<PhysicalDevice, VulkanError> {
         let mut selected = None;
@@ -213,14 +408,74 @@ impl Device {
         Some(d)
     }
     #[inline]
-    unsafe fn init(extensions: &[*const c_char]) -> Result<(ash::Entry, Instance), VulkanError> {
-        let entry = ash::Entry::load()?;
+    unsafe fn init(
+        entry: &ash::Entry,
+        extensions: &ExtensionSet,
+    ) -> Result<(Instance, Option<DebugMessenger>), VulkanError> {
         let app_info = vk::ApplicationInfo::default().api_version(vk::API_VERSION_1_0);
+
+        #[cfg(debug_assertions)]
+        let layers = {
+            let mut requested = LayerSet::new();
+            requested.push("VK_LAYER_KHRONOS_validation")?;
+            let available = entry.enumerate_instance_layer_properties()?;
+            if requested.all_supported(&available) {
+                requested
+            } else {
+                println!("Validation layer requested but not available - skipping");
+                LayerSet::new()
+            }
+        };
+        #[cfg(not(debug_assertions))]
+        let layers = LayerSet::new();
+
+        let mut extensions = extensions.clone();
+        #[cfg(debug_assertions)]
+        if !layers.is_empty() {
+            extensions.push_cstr(ash::ext::debug_utils::NAME);
+        }
+
+        let extension_ptrs = extensions.as_ptrs();
+        let layer_ptrs = layers.as_ptrs();
         let create_info = vk::InstanceCreateInfo::default()
             .application_info(&app_info)
-            .enabled_extension_names(extensions);
+            .enabled_extension_names(&extension_ptrs)
+            .enabled_layer_names(&layer_ptrs);
         let instance = entry.create_instance(&create_info, None)?;
-        Ok((entry, instance))
+
+        #[cfg(debug_assertions)]
+        let debug_messenger = if !layers.is_empty() {
+            Some(Self::init_debug_messenger(entry, &instance)?)
+        } else {
+            None
+        };
+        #[cfg(not(debug_assertions))]
+        let debug_messenger = None;
+
+        Ok((instance, debug_messenger))
+    }
+
+    #[cfg(debug_assertions)]
+    #[inline]
+    unsafe fn init_debug_messenger(
+        entry: &ash::Entry,
+        instance: &Instance,
+    ) -> Result<DebugMessenger, VulkanError> {
+        let loader = ash::ext::debug_utils::Instance::new(entry, instance);
+        let create_info = vk::DebugUtilsMessengerCreateInfoEXT::default()
+            .message_severity(
+                vk::DebugUtilsMessageSeverityFlagsEXT::ERROR
+                    | vk::DebugUtilsMessageSeverityFlagsEXT::WARNING
+                    | vk::DebugUtilsMessageSeverityFlagsEXT::INFO,
+            )
+            .message_type(
+                vk::DebugUtilsMessageTypeFlagsEXT::GENERAL
+                    | vk::DebugUtilsMessageTypeFlagsEXT::VALIDATION
+                    | vk::DebugUtilsMessageTypeFlagsEXT::PERFORMANCE,
+            )
+            .pfn_user_callback(Some(vulkan_debug_callback));
+        let messenger = loader.create_debug_utils_messenger(&create_info, None)?;
+        Ok(DebugMessenger { loader, messenger })
     }
 
     #[inline]
@@ -228,40 +483,70 @@ impl Device {
         instance: &Instance,
         physical: PhysicalDevice,
         extensions: &[*const c_char],
-        queue_family_index: u32,
-    ) -> Result<ash::Device, VulkanError> {
+        graphics_family: u32,
+        present_family: u32,
+    ) -> Result<(ash::Device, vk::Queue, vk::Queue), VulkanError> {
+        let unique_families: HashSet<u32> = HashSet::from([graphics_family, present_family]);
+        let queue_priorities = [1.0f32];
+        let queue_infos: Vec<vk::DeviceQueueCreateInfo> = unique_families
+            .iter()
+            .map(|&family| {
+                vk::DeviceQueueCreateInfo::default()
+                    .queue_family_index(family)
+                    .queue_priorities(&queue_priorities)
+            })
+            .collect();
         let features = vk::PhysicalDeviceFeatures::default();
-        let queue_info =
-            vk::DeviceQueueCreateInfo::default().queue_family_index(queue_family_index);
         let create_info = vk::DeviceCreateInfo::default()
-            .queue_create_infos(std::slice::from_ref(&queue_info))
+            .queue_create_infos(&queue_infos)
             .enabled_extension_names(extensions)
             .enabled_features(&features);
-        Ok(instance.create_device(physical, &create_info, None)?)
+        let logical = instance.create_device(physical, &create_info, None)?;
+        let graphics_queue = logical.get_device_queue(graphics_family, 0);
+        let present_queue = logical.get_device_queue(present_family, 0);
+        Ok((logical, graphics_queue, present_queue))
     }
 
+    /// Finds a queue family that supports graphics and one that can present to
+    /// `surface`, preferring a single family that can do both.
     #[inline]
-    unsafe fn find_queue_family_index(
+    unsafe fn find_queue_families(
         instance: &Instance,
         physical: PhysicalDevice,
-    ) -> Option<u32> {
+        surface_loader: &surface::Instance,
+        surface: vk::SurfaceKHR,
+    ) -> Result<(u32, u32), VulkanError> {
         let queue_families = instance.get_physical_device_queue_family_properties(physical);
-        let mut index = 0;
-        for queue_family in queue_families {
-            if queue_family.queue_flags.contains(vk::QueueFlags::GRAPHICS) {
-                return Some(index);
+        let mut graphics_family = None;
+        let mut present_family = None;
+        let mut shared_family = None;
+        for (index, family) in queue_families.iter().enumerate() {
+            let index = index as u32;
+            let supports_graphics = family.queue_flags.contains(vk::QueueFlags::GRAPHICS);
+            let supports_present =
+                surface_loader.get_physical_device_surface_support(physical, index, surface)?;
+            if supports_graphics && supports_present && shared_family.is_none() {
+                shared_family = Some(index);
             }
-            index += 1;
+            if supports_graphics && graphics_family.is_none() {
+                graphics_family = Some(index);
+            }
+            if supports_present && present_family.is_none() {
+                present_family = Some(index);
+            }
+        }
+        if let Some(shared) = shared_family {
+            return Ok((shared, shared));
+        }
+        match (graphics_family, present_family) {
+            (Some(graphics), Some(present)) => Ok((graphics, present)),
+            _ => Err(VulkanError::UnableToFindQueueFamily),
         }
-        None
     }
 
     // Surface and swapchain stuff
 
-    pub fn link_to_window(
-        &self,
-        window: &Window,
-    ) -> Result<(vk::SurfaceKHR, SwapchainKHR), VulkanError> {
+    pub fn link_to_window(&mut self, window: &Window) -> Result<SwapchainSetup, VulkanError> {
         let surface = unsafe {
             ash_window::create_surface(
                 &self.entry,
@@ -271,18 +556,927 @@ impl Device {
                 None,
             )?
         };
-        // TODO: Handle queue families properly instead of doing it "lazily" and definitely incorrectly.
-        let create_info = SwapchainCreateInfoKHR::default().surface(surface);
-        let swapchain = unsafe { self.swapchain_loader.create_swapchain(&create_info, None)? };
-        Ok((surface, swapchain))
+        let surface_loader = surface::Instance::new(&self.entry, &self.instance);
+
+        if self.logical.is_none() {
+            let (graphics_family, present_family) = unsafe {
+                Self::find_queue_families(&self.instance, self.physical, &surface_loader, surface)?
+            };
+            let device_extension_ptrs = self.device_extensions.as_ptrs();
+            let (logical, graphics_queue, present_queue) = unsafe {
+                Self::create_logical(
+                    &self.instance,
+                    self.physical,
+                    &device_extension_ptrs,
+                    graphics_family,
+                    present_family,
+                )?
+            };
+            self.swapchain_loader = Some(swapchain::Device::new(&self.instance, &logical));
+            self.logical = Some(logical);
+            self.graphics_queue_family = Some(graphics_family);
+            self.present_queue_family = Some(present_family);
+            self.graphics_queue = Some(graphics_queue);
+            self.present_queue = Some(present_queue);
+        }
+
+        let capabilities = unsafe {
+            surface_loader.get_physical_device_surface_capabilities(self.physical, surface)?
+        };
+        let formats =
+            unsafe { surface_loader.get_physical_device_surface_formats(self.physical, surface)? };
+        let present_modes = unsafe {
+            surface_loader.get_physical_device_surface_present_modes(self.physical, surface)?
+        };
+
+        let surface_format = formats
+            .iter()
+            .find(|f| {
+                f.format == vk::Format::B8G8R8A8_SRGB
+                    && f.color_space == vk::ColorSpaceKHR::SRGB_NONLINEAR
+            })
+            .copied()
+            .unwrap_or(formats[0]);
+
+        let present_mode = present_modes
+            .iter()
+            .copied()
+            .find(|&mode| mode == vk::PresentModeKHR::MAILBOX)
+            .unwrap_or(vk::PresentModeKHR::FIFO);
+
+        let extent = if capabilities.current_extent.width != u32::MAX {
+            capabilities.current_extent
+        } else {
+            let size = window.inner_size();
+            vk::Extent2D {
+                width: size.width.clamp(
+                    capabilities.min_image_extent.width,
+                    capabilities.max_image_extent.width,
+                ),
+                height: size.height.clamp(
+                    capabilities.min_image_extent.height,
+                    capabilities.max_image_extent.height,
+                ),
+            }
+        };
+
+        let mut min_image_count = capabilities.min_image_count + 1;
+        if capabilities.max_image_count > 0 && min_image_count > capabilities.max_image_count {
+            min_image_count = capabilities.max_image_count;
+        }
+
+        let graphics_family = self.graphics_queue_family.unwrap();
+        let present_family = self.present_queue_family.unwrap();
+        let queue_family_indices = [graphics_family, present_family];
+
+        let mut create_info = SwapchainCreateInfoKHR::default()
+            .surface(surface)
+            .min_image_count(min_image_count)
+            .image_format(surface_format.format)
+            .image_color_space(surface_format.color_space)
+            .image_extent(extent)
+            .image_array_layers(1)
+            .image_usage(vk::ImageUsageFlags::COLOR_ATTACHMENT)
+            .pre_transform(capabilities.current_transform)
+            .composite_alpha(vk::CompositeAlphaFlagsKHR::OPAQUE)
+            .present_mode(present_mode)
+            .clipped(true);
+        create_info = if graphics_family != present_family {
+            create_info
+                .image_sharing_mode(vk::SharingMode::CONCURRENT)
+                .queue_family_indices(&queue_family_indices)
+        } else {
+            create_info.image_sharing_mode(vk::SharingMode::EXCLUSIVE)
+        };
+
+        let swapchain_loader = self.swapchain_loader.as_ref().unwrap();
+        let swapchain = unsafe { swapchain_loader.create_swapchain(&create_info, None)? };
+
+        let images = unsafe { swapchain_loader.get_swapchain_images(swapchain)? };
+        let image_views = images
+            .iter()
+            .map(|&image| unsafe { self.create_image_view(image, surface_format.format) })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(SwapchainSetup {
+            surface,
+            swapchain,
+            format: surface_format.format,
+            extent,
+            images,
+            image_views,
+        })
+    }
+
+    #[inline]
+    unsafe fn create_image_view(
+        &self,
+        image: vk::Image,
+        format: vk::Format,
+    ) -> Result<vk::ImageView, VulkanError> {
+        let create_info = vk::ImageViewCreateInfo::default()
+            .image(image)
+            .view_type(vk::ImageViewType::TYPE_2D)
+            .format(format)
+            .subresource_range(vk::ImageSubresourceRange {
+                aspect_mask: vk::ImageAspectFlags::COLOR,
+                base_mip_level: 0,
+                level_count: 1,
+                base_array_layer: 0,
+                layer_count: 1,
+            });
+        Ok(self
+            .logical
+            .as_ref()
+            .unwrap()
+            .create_image_view(&create_info, None)?)
+    }
+
+    pub(crate) fn logical_handle(&self) -> ash::Device {
+        self.logical.as_ref().unwrap().clone()
+    }
+
+    pub(crate) fn swapchain_loader_handle(&self) -> swapchain::Device {
+        self.swapchain_loader.as_ref().unwrap().clone()
+    }
+
+    pub(crate) fn surface_loader(&self) -> surface::Instance {
+        surface::Instance::new(&self.entry, &self.instance)
+    }
+
+    // Render pass, pipeline and per-frame rendering resources.
+
+    pub(crate) fn create_render_pass(&self, format: vk::Format) -> Result<vk::RenderPass, VulkanError> {
+        let color_attachment = vk::AttachmentDescription::default()
+            .format(format)
+            .samples(vk::SampleCountFlags::TYPE_1)
+            .load_op(vk::AttachmentLoadOp::CLEAR)
+            .store_op(vk::AttachmentStoreOp::STORE)
+            .stencil_load_op(vk::AttachmentLoadOp::DONT_CARE)
+            .stencil_store_op(vk::AttachmentStoreOp::DONT_CARE)
+            .initial_layout(vk::ImageLayout::UNDEFINED)
+            .final_layout(vk::ImageLayout::PRESENT_SRC_KHR);
+        let color_attachment_ref = vk::AttachmentReference::default()
+            .attachment(0)
+            .layout(vk::ImageLayout::COLOR_ATTACHMENT_OPTIMAL);
+        let color_attachment_refs = [color_attachment_ref];
+        let subpass = vk::SubpassDescription::default()
+            .pipeline_bind_point(vk::PipelineBindPoint::GRAPHICS)
+            .color_attachments(&color_attachment_refs);
+        let dependency = vk::SubpassDependency::default()
+            .src_subpass(vk::SUBPASS_EXTERNAL)
+            .dst_subpass(0)
+            .src_stage_mask(vk::PipelineStageFlags::COLOR_ATTACHMENT_OUTPUT)
+            .dst_stage_mask(vk::PipelineStageFlags::COLOR_ATTACHMENT_OUTPUT)
+            .dst_access_mask(vk::AccessFlags::COLOR_ATTACHMENT_WRITE);
+        let attachments = [color_attachment];
+        let subpasses = [subpass];
+        let dependencies = [dependency];
+        let create_info = vk::RenderPassCreateInfo::default()
+            .attachments(&attachments)
+            .subpasses(&subpasses)
+            .dependencies(&dependencies);
+        Ok(unsafe {
+            self.logical
+                .as_ref()
+                .unwrap()
+                .create_render_pass(&create_info, None)?
+        })
+    }
+
+    unsafe fn create_shader_module(&self, code: &[u8]) -> Result<vk::ShaderModule, VulkanError> {
+        let words = ash::util::read_spv(&mut std::io::Cursor::new(code)).expect("Invalid SPIR-V");
+        let create_info = vk::ShaderModuleCreateInfo::default().code(&words);
+        Ok(self
+            .logical
+            .as_ref()
+            .unwrap()
+            .create_shader_module(&create_info, None)?)
+    }
+
+    pub(crate) fn create_pipeline(
+        &self,
+        render_pass: vk::RenderPass,
+        descriptor_set_layout: vk::DescriptorSetLayout,
+    ) -> Result<(vk::PipelineLayout, vk::Pipeline), VulkanError> {
+        let logical = self.logical.as_ref().unwrap();
+        let vert_module = unsafe { self.create_shader_module(SCENE_VERT_SPV)? };
+        let frag_module = unsafe { self.create_shader_module(SCENE_FRAG_SPV)? };
+        let entry_point = CString::new("main").unwrap();
+
+        let stages = [
+            vk::PipelineShaderStageCreateInfo::default()
+                .stage(vk::ShaderStageFlags::VERTEX)
+                .module(vert_module)
+                .name(&entry_point),
+            vk::PipelineShaderStageCreateInfo::default()
+                .stage(vk::ShaderStageFlags::FRAGMENT)
+                .module(frag_module)
+                .name(&entry_point),
+        ];
+
+        let vertex_input = vk::PipelineVertexInputStateCreateInfo::default();
+        let input_assembly = vk::PipelineInputAssemblyStateCreateInfo::default()
+            .topology(vk::PrimitiveTopology::TRIANGLE_LIST);
+
+        let viewport_state = vk::PipelineViewportStateCreateInfo::default()
+            .viewport_count(1)
+            .scissor_count(1);
+
+        let rasterizer = vk::PipelineRasterizationStateCreateInfo::default()
+            .polygon_mode(vk::PolygonMode::FILL)
+            .line_width(1.0)
+            .cull_mode(vk::CullModeFlags::BACK)
+            .front_face(vk::FrontFace::CLOCKWISE);
+
+        let multisampling = vk::PipelineMultisampleStateCreateInfo::default()
+            .rasterization_samples(vk::SampleCountFlags::TYPE_1);
+
+        let color_blend_attachment = vk::PipelineColorBlendAttachmentState::default()
+            .color_write_mask(vk::ColorComponentFlags::RGBA)
+            .blend_enable(false);
+        let color_blend_attachments = [color_blend_attachment];
+        let color_blending =
+            vk::PipelineColorBlendStateCreateInfo::default().attachments(&color_blend_attachments);
+
+        let dynamic_states = [vk::DynamicState::VIEWPORT, vk::DynamicState::SCISSOR];
+        let dynamic_state =
+            vk::PipelineDynamicStateCreateInfo::default().dynamic_states(&dynamic_states);
+
+        let set_layouts = [descriptor_set_layout];
+        let layout_create_info =
+            vk::PipelineLayoutCreateInfo::default().set_layouts(&set_layouts);
+        let pipeline_layout = unsafe { logical.create_pipeline_layout(&layout_create_info, None)? };
+
+        let pipeline_create_info = vk::GraphicsPipelineCreateInfo::default()
+            .stages(&stages)
+            .vertex_input_state(&vertex_input)
+            .input_assembly_state(&input_assembly)
+            .viewport_state(&viewport_state)
+            .rasterization_state(&rasterizer)
+            .multisample_state(&multisampling)
+            .color_blend_state(&color_blending)
+            .dynamic_state(&dynamic_state)
+            .layout(pipeline_layout)
+            .render_pass(render_pass)
+            .subpass(0);
+
+        let pipeline = unsafe {
+            logical
+                .create_graphics_pipelines(vk::PipelineCache::null(), &[pipeline_create_info], None)
+                .map_err(|(_, err)| err)?
+        }[0];
+
+        unsafe {
+            logical.destroy_shader_module(vert_module, None);
+            logical.destroy_shader_module(frag_module, None);
+        }
+
+        Ok((pipeline_layout, pipeline))
+    }
+
+    /// Builds the HUD's pipeline: alpha-blended glyph quads over the scene,
+    /// sharing `render_pass` so no extra attachments or passes are needed.
+    /// There is no depth attachment at all in this render pass, so the HUD
+    /// is implicitly never depth-tested. The orthographic projection is
+    /// pushed per-frame as a push constant since it only depends on the
+    /// current swapchain extent.
+    pub(crate) fn create_overlay_pipeline(
+        &self,
+        render_pass: vk::RenderPass,
+    ) -> Result<(vk::PipelineLayout, vk::Pipeline), VulkanError> {
+        let logical = self.logical.as_ref().unwrap();
+        let vert_module = unsafe { self.create_shader_module(OVERLAY_VERT_SPV)? };
+        let frag_module = unsafe { self.create_shader_module(OVERLAY_FRAG_SPV)? };
+        let entry_point = CString::new("main").unwrap();
+
+        let stages = [
+            vk::PipelineShaderStageCreateInfo::default()
+                .stage(vk::ShaderStageFlags::VERTEX)
+                .module(vert_module)
+                .name(&entry_point),
+            vk::PipelineShaderStageCreateInfo::default()
+                .stage(vk::ShaderStageFlags::FRAGMENT)
+                .module(frag_module)
+                .name(&entry_point),
+        ];
+
+        let binding_description = vk::VertexInputBindingDescription::default()
+            .binding(0)
+            .stride(size_of::<OverlayVertex>() as u32)
+            .input_rate(vk::VertexInputRate::VERTEX);
+        let bindings = [binding_description];
+        let attribute_descriptions = [
+            vk::VertexInputAttributeDescription::default()
+                .binding(0)
+                .location(0)
+                .format(vk::Format::R32G32_SFLOAT)
+                .offset(0),
+            vk::VertexInputAttributeDescription::default()
+                .binding(0)
+                .location(1)
+                .format(vk::Format::R32G32B32A32_SFLOAT)
+                .offset(size_of::<[f32; 2]>() as u32),
+        ];
+        let vertex_input = vk::PipelineVertexInputStateCreateInfo::default()
+            .vertex_binding_descriptions(&bindings)
+            .vertex_attribute_descriptions(&attribute_descriptions);
+        let input_assembly = vk::PipelineInputAssemblyStateCreateInfo::default()
+            .topology(vk::PrimitiveTopology::TRIANGLE_LIST);
+
+        let viewport_state = vk::PipelineViewportStateCreateInfo::default()
+            .viewport_count(1)
+            .scissor_count(1);
+
+        let rasterizer = vk::PipelineRasterizationStateCreateInfo::default()
+            .polygon_mode(vk::PolygonMode::FILL)
+            .line_width(1.0)
+            .cull_mode(vk::CullModeFlags::NONE);
+
+        let multisampling = vk::PipelineMultisampleStateCreateInfo::default()
+            .rasterization_samples(vk::SampleCountFlags::TYPE_1);
+
+        let color_blend_attachment = vk::PipelineColorBlendAttachmentState::default()
+            .color_write_mask(vk::ColorComponentFlags::RGBA)
+            .blend_enable(true)
+            .src_color_blend_factor(vk::BlendFactor::SRC_ALPHA)
+            .dst_color_blend_factor(vk::BlendFactor::ONE_MINUS_SRC_ALPHA)
+            .color_blend_op(vk::BlendOp::ADD)
+            .src_alpha_blend_factor(vk::BlendFactor::ONE)
+            .dst_alpha_blend_factor(vk::BlendFactor::ONE_MINUS_SRC_ALPHA)
+            .alpha_blend_op(vk::BlendOp::ADD);
+        let color_blend_attachments = [color_blend_attachment];
+        let color_blending =
+            vk::PipelineColorBlendStateCreateInfo::default().attachments(&color_blend_attachments);
+
+        let dynamic_states = [vk::DynamicState::VIEWPORT, vk::DynamicState::SCISSOR];
+        let dynamic_state =
+            vk::PipelineDynamicStateCreateInfo::default().dynamic_states(&dynamic_states);
+
+        let push_constant_range = vk::PushConstantRange::default()
+            .stage_flags(vk::ShaderStageFlags::VERTEX)
+            .offset(0)
+            .size(size_of::<glm::Mat4>() as u32);
+        let push_constant_ranges = [push_constant_range];
+        let layout_create_info =
+            vk::PipelineLayoutCreateInfo::default().push_constant_ranges(&push_constant_ranges);
+        let pipeline_layout = unsafe { logical.create_pipeline_layout(&layout_create_info, None)? };
+
+        let pipeline_create_info = vk::GraphicsPipelineCreateInfo::default()
+            .stages(&stages)
+            .vertex_input_state(&vertex_input)
+            .input_assembly_state(&input_assembly)
+            .viewport_state(&viewport_state)
+            .rasterization_state(&rasterizer)
+            .multisample_state(&multisampling)
+            .color_blend_state(&color_blending)
+            .dynamic_state(&dynamic_state)
+            .layout(pipeline_layout)
+            .render_pass(render_pass)
+            .subpass(0);
+
+        let pipeline = unsafe {
+            logical
+                .create_graphics_pipelines(vk::PipelineCache::null(), &[pipeline_create_info], None)
+                .map_err(|(_, err)| err)?
+        }[0];
+
+        unsafe {
+            logical.destroy_shader_module(vert_module, None);
+            logical.destroy_shader_module(frag_module, None);
+        }
+
+        Ok((pipeline_layout, pipeline))
+    }
+
+    pub(crate) fn create_framebuffers(
+        &self,
+        render_pass: vk::RenderPass,
+        image_views: &[vk::ImageView],
+        extent: vk::Extent2D,
+    ) -> Result<Vec<vk::Framebuffer>, VulkanError> {
+        let logical = self.logical.as_ref().unwrap();
+        image_views
+            .iter()
+            .map(|&image_view| {
+                let attachments = [image_view];
+                let create_info = vk::FramebufferCreateInfo::default()
+                    .render_pass(render_pass)
+                    .attachments(&attachments)
+                    .width(extent.width)
+                    .height(extent.height)
+                    .layers(1);
+                unsafe { logical.create_framebuffer(&create_info, None) }.map_err(VulkanError::from)
+            })
+            .collect()
+    }
+
+    pub(crate) fn create_command_pool(&self) -> Result<vk::CommandPool, VulkanError> {
+        let create_info = vk::CommandPoolCreateInfo::default()
+            .flags(vk::CommandPoolCreateFlags::RESET_COMMAND_BUFFER)
+            .queue_family_index(self.graphics_queue_family.unwrap());
+        Ok(unsafe {
+            self.logical
+                .as_ref()
+                .unwrap()
+                .create_command_pool(&create_info, None)?
+        })
+    }
+
+    pub(crate) fn create_command_buffers(
+        &self,
+        command_pool: vk::CommandPool,
+        count: u32,
+    ) -> Result<Vec<vk::CommandBuffer>, VulkanError> {
+        let alloc_info = vk::CommandBufferAllocateInfo::default()
+            .command_pool(command_pool)
+            .level(vk::CommandBufferLevel::PRIMARY)
+            .command_buffer_count(count);
+        Ok(unsafe {
+            self.logical
+                .as_ref()
+                .unwrap()
+                .allocate_command_buffers(&alloc_info)?
+        })
+    }
+
+    pub(crate) fn create_sync_objects(&self) -> Result<SyncObjects, VulkanError> {
+        let logical = self.logical.as_ref().unwrap();
+        let semaphore_info = vk::SemaphoreCreateInfo::default();
+        let fence_info = vk::FenceCreateInfo::default().flags(vk::FenceCreateFlags::SIGNALED);
+        let mut image_available = Vec::with_capacity(MAX_FRAMES_IN_FLIGHT);
+        let mut render_finished = Vec::with_capacity(MAX_FRAMES_IN_FLIGHT);
+        let mut in_flight = Vec::with_capacity(MAX_FRAMES_IN_FLIGHT);
+        for _ in 0..MAX_FRAMES_IN_FLIGHT {
+            unsafe {
+                image_available.push(logical.create_semaphore(&semaphore_info, None)?);
+                render_finished.push(logical.create_semaphore(&semaphore_info, None)?);
+                in_flight.push(logical.create_fence(&fence_info, None)?);
+            }
+        }
+        Ok((image_available, render_finished, in_flight))
+    }
+
+    // Binds the Vulkan handles for the scene draw and the optional HUD draw; splitting it into a
+    // struct would just move the same arguments one level out.
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn record_command_buffer(
+        &self,
+        command_buffer: vk::CommandBuffer,
+        render_pass: vk::RenderPass,
+        framebuffer: vk::Framebuffer,
+        pipeline: vk::Pipeline,
+        pipeline_layout: vk::PipelineLayout,
+        descriptor_set: vk::DescriptorSet,
+        overlay: Option<&OverlayDraw>,
+        extent: vk::Extent2D,
+    ) -> Result<(), VulkanError> {
+        let logical = self.logical.as_ref().unwrap();
+        let begin_info = vk::CommandBufferBeginInfo::default();
+        let clear_values = [vk::ClearValue {
+            color: vk::ClearColorValue {
+                float32: [0.0, 0.0, 0.0, 1.0],
+            },
+        }];
+        let render_area = vk::Rect2D::default().extent(extent);
+        let render_pass_begin = vk::RenderPassBeginInfo::default()
+            .render_pass(render_pass)
+            .framebuffer(framebuffer)
+            .render_area(render_area)
+            .clear_values(&clear_values);
+        let viewport = vk::Viewport::default()
+            .x(0.0)
+            .y(0.0)
+            .width(extent.width as f32)
+            .height(extent.height as f32)
+            .min_depth(0.0)
+            .max_depth(1.0);
+        let scissor = vk::Rect2D::default().extent(extent);
+        unsafe {
+            logical.begin_command_buffer(command_buffer, &begin_info)?;
+            logical.cmd_begin_render_pass(
+                command_buffer,
+                &render_pass_begin,
+                vk::SubpassContents::INLINE,
+            );
+            logical.cmd_bind_pipeline(command_buffer, vk::PipelineBindPoint::GRAPHICS, pipeline);
+            logical.cmd_bind_descriptor_sets(
+                command_buffer,
+                vk::PipelineBindPoint::GRAPHICS,
+                pipeline_layout,
+                0,
+                &[descriptor_set],
+                &[],
+            );
+            logical.cmd_set_viewport(command_buffer, 0, &[viewport]);
+            logical.cmd_set_scissor(command_buffer, 0, &[scissor]);
+            logical.cmd_draw(command_buffer, 6, 1, 0, 0);
+            if let Some(overlay) = overlay {
+                logical.cmd_bind_pipeline(
+                    command_buffer,
+                    vk::PipelineBindPoint::GRAPHICS,
+                    overlay.pipeline,
+                );
+                let push_constants = std::slice::from_raw_parts(
+                    &overlay.projection as *const glm::Mat4 as *const u8,
+                    size_of::<glm::Mat4>(),
+                );
+                logical.cmd_push_constants(
+                    command_buffer,
+                    overlay.pipeline_layout,
+                    vk::ShaderStageFlags::VERTEX,
+                    0,
+                    push_constants,
+                );
+                logical.cmd_bind_vertex_buffers(command_buffer, 0, &[overlay.vertex_buffer], &[0]);
+                if overlay.vertex_count > 0 {
+                    logical.cmd_draw(command_buffer, overlay.vertex_count, 1, 0, 0);
+                }
+            }
+            logical.cmd_end_render_pass(command_buffer);
+            logical.end_command_buffer(command_buffer)?;
+        }
+        Ok(())
+    }
+
+    /// Waits on the frame's fence, acquires a swapchain image, submits the
+    /// recorded command buffer and presents. Recreates the swapchain on
+    /// `ERROR_OUT_OF_DATE_KHR`/`SUBOPTIMAL_KHR` instead of surfacing them.
+    pub fn draw_frame(
+        &mut self,
+        view: &mut WindowView,
+        camera: &Camera,
+        overlay: &mut crate::overlay::Overlay,
+    ) -> Result<(), VulkanError> {
+        let logical = self.logical.as_ref().unwrap();
+        let fence = view.in_flight_fences[view.current_frame];
+        unsafe { logical.wait_for_fences(&[fence], true, u64::MAX)? };
+
+        let swapchain_loader = self.swapchain_loader.as_ref().unwrap();
+        let image_available = view.image_available_semaphores[view.current_frame];
+        let image_index = match unsafe {
+            swapchain_loader.acquire_next_image(
+                view.swapchain,
+                u64::MAX,
+                image_available,
+                vk::Fence::null(),
+            )
+        } {
+            Ok((index, _suboptimal)) => index,
+            Err(vk::Result::ERROR_OUT_OF_DATE_KHR) => {
+                return self.recreate_swapchain(view);
+            }
+            Err(err) => return Err(err.into()),
+        };
+
+        // The swapchain can have more images than MAX_FRAMES_IN_FLIGHT, so the
+        // image we just acquired may still be read by a submission tracked
+        // under a different frame's fence. Wait on whichever fence last used
+        // it before reusing its command buffer.
+        let image_fence = view.images_in_flight[image_index as usize];
+        if image_fence != vk::Fence::null() {
+            unsafe { logical.wait_for_fences(&[image_fence], true, u64::MAX)? };
+        }
+        view.images_in_flight[image_index as usize] = fence;
+
+        unsafe { logical.reset_fences(&[fence])? };
+
+        self.update_uniform_buffer(view, camera);
+
+        overlay.drain();
+        let overlay_vertices =
+            overlay.build_vertices(view.extent.width as f32, view.extent.height as f32);
+        let overlay_draw = OverlayDraw {
+            pipeline: view.overlay_pipeline,
+            pipeline_layout: view.overlay_pipeline_layout,
+            vertex_buffer: view.overlay_vertex_buffers[view.current_frame],
+            vertex_count: self.update_overlay_vertex_buffer(view, &overlay_vertices),
+            projection: glm::ortho(
+                0.0,
+                view.extent.width as f32,
+                0.0,
+                view.extent.height as f32,
+                -1.0,
+                1.0,
+            ),
+        };
+
+        let render_finished = view.render_finished_semaphores[view.current_frame];
+        let command_buffer = view.command_buffers[image_index as usize];
+        unsafe {
+            logical.reset_command_buffer(command_buffer, vk::CommandBufferResetFlags::empty())?;
+        }
+        self.record_command_buffer(
+            command_buffer,
+            view.render_pass,
+            view.framebuffers[image_index as usize],
+            view.pipeline,
+            view.pipeline_layout,
+            view.descriptor_sets[view.current_frame],
+            Some(&overlay_draw),
+            view.extent,
+        )?;
+
+        let wait_semaphores = [image_available];
+        let wait_stages = [vk::PipelineStageFlags::COLOR_ATTACHMENT_OUTPUT];
+        let signal_semaphores = [render_finished];
+        let command_buffers = [command_buffer];
+        let submit_info = vk::SubmitInfo::default()
+            .wait_semaphores(&wait_semaphores)
+            .wait_dst_stage_mask(&wait_stages)
+            .command_buffers(&command_buffers)
+            .signal_semaphores(&signal_semaphores);
+        unsafe {
+            logical.queue_submit(self.graphics_queue.unwrap(), &[submit_info], fence)?;
+        }
+
+        let swapchains = [view.swapchain];
+        let image_indices = [image_index];
+        let present_info = vk::PresentInfoKHR::default()
+            .wait_semaphores(&signal_semaphores)
+            .swapchains(&swapchains)
+            .image_indices(&image_indices);
+        match unsafe { swapchain_loader.queue_present(self.present_queue.unwrap(), &present_info) } {
+            Ok(suboptimal) if suboptimal => return self.recreate_swapchain(view),
+            Ok(_) => {}
+            Err(vk::Result::ERROR_OUT_OF_DATE_KHR) => return self.recreate_swapchain(view),
+            Err(err) => return Err(err.into()),
+        }
+
+        view.current_frame = (view.current_frame + 1) % MAX_FRAMES_IN_FLIGHT;
+        Ok(())
+    }
+
+    /// Rebuilds the swapchain, image views, framebuffers and command buffers
+    /// for `view` in place, e.g. after a resize or `ERROR_OUT_OF_DATE_KHR`.
+    pub fn recreate_swapchain(&mut self, view: &mut WindowView) -> Result<(), VulkanError> {
+        let logical = self.logical.as_ref().unwrap();
+        unsafe { logical.device_wait_idle()? };
+
+        unsafe {
+            for &framebuffer in &view.framebuffers {
+                logical.destroy_framebuffer(framebuffer, None);
+            }
+            for &image_view in &view.image_views {
+                logical.destroy_image_view(image_view, None);
+            }
+            self.swapchain_loader
+                .as_ref()
+                .unwrap()
+                .destroy_swapchain(view.swapchain, None);
+            self.surface_loader().destroy_surface(view.surface, None);
+        }
+
+        let setup = self.link_to_window(&view.window)?;
+        view.surface = setup.surface;
+        view.swapchain = setup.swapchain;
+        view.images = setup.images;
+        view.image_views = setup.image_views;
+        view.format = setup.format;
+        view.extent = setup.extent;
+        view.images_in_flight = vec![vk::Fence::null(); view.images.len()];
+
+        view.framebuffers =
+            self.create_framebuffers(view.render_pass, &view.image_views, view.extent)?;
+        for (index, &command_buffer) in view.command_buffers.iter().enumerate() {
+            // The overlay mesh is rebuilt and bound fresh in `draw_frame` every
+            // frame, so it's safe to leave it out of this one-off re-record.
+            self.record_command_buffer(
+                command_buffer,
+                view.render_pass,
+                view.framebuffers[index],
+                view.pipeline,
+                view.pipeline_layout,
+                view.descriptor_sets[0],
+                None,
+                view.extent,
+            )?;
+        }
+        view.needs_resize = false;
+        Ok(())
+    }
+
+    // Descriptor sets and uniform buffers for the camera UBO.
+
+    pub(crate) fn create_descriptor_set_layout(
+        &self,
+    ) -> Result<vk::DescriptorSetLayout, VulkanError> {
+        let binding = vk::DescriptorSetLayoutBinding::default()
+            .binding(0)
+            .descriptor_type(vk::DescriptorType::UNIFORM_BUFFER)
+            .descriptor_count(1)
+            .stage_flags(vk::ShaderStageFlags::VERTEX);
+        let bindings = [binding];
+        let create_info = vk::DescriptorSetLayoutCreateInfo::default().bindings(&bindings);
+        Ok(unsafe {
+            self.logical
+                .as_ref()
+                .unwrap()
+                .create_descriptor_set_layout(&create_info, None)?
+        })
+    }
+
+    pub(crate) fn create_descriptor_pool(&self) -> Result<vk::DescriptorPool, VulkanError> {
+        let pool_size = vk::DescriptorPoolSize::default()
+            .ty(vk::DescriptorType::UNIFORM_BUFFER)
+            .descriptor_count(MAX_FRAMES_IN_FLIGHT as u32);
+        let pool_sizes = [pool_size];
+        let create_info = vk::DescriptorPoolCreateInfo::default()
+            .pool_sizes(&pool_sizes)
+            .max_sets(MAX_FRAMES_IN_FLIGHT as u32);
+        Ok(unsafe {
+            self.logical
+                .as_ref()
+                .unwrap()
+                .create_descriptor_pool(&create_info, None)?
+        })
+    }
+
+    pub(crate) fn create_descriptor_sets(
+        &self,
+        descriptor_pool: vk::DescriptorPool,
+        descriptor_set_layout: vk::DescriptorSetLayout,
+        uniform_buffers: &[vk::Buffer],
+    ) -> Result<Vec<vk::DescriptorSet>, VulkanError> {
+        let logical = self.logical.as_ref().unwrap();
+        let layouts = vec![descriptor_set_layout; MAX_FRAMES_IN_FLIGHT];
+        let alloc_info = vk::DescriptorSetAllocateInfo::default()
+            .descriptor_pool(descriptor_pool)
+            .set_layouts(&layouts);
+        let descriptor_sets = unsafe { logical.allocate_descriptor_sets(&alloc_info)? };
+        for (&descriptor_set, &buffer) in descriptor_sets.iter().zip(uniform_buffers.iter()) {
+            let buffer_info = vk::DescriptorBufferInfo::default()
+                .buffer(buffer)
+                .offset(0)
+                .range(size_of::<CameraUbo>() as u64);
+            let buffer_infos = [buffer_info];
+            let write = vk::WriteDescriptorSet::default()
+                .dst_set(descriptor_set)
+                .dst_binding(0)
+                .dst_array_element(0)
+                .descriptor_type(vk::DescriptorType::UNIFORM_BUFFER)
+                .buffer_info(&buffer_infos);
+            unsafe { logical.update_descriptor_sets(&[write], &[]) };
+        }
+        Ok(descriptor_sets)
+    }
+
+    /// Finds a memory type index satisfying both `filter` (from the buffer's
+    /// memory requirements) and the requested `properties`.
+    unsafe fn find_memory_type(
+        &self,
+        filter: u32,
+        properties: vk::MemoryPropertyFlags,
+    ) -> Result<u32, VulkanError> {
+        let memory_properties = self
+            .instance
+            .get_physical_device_memory_properties(self.physical);
+        for i in 0..memory_properties.memory_type_count {
+            let suitable = filter & (1 << i) != 0;
+            let has_properties = memory_properties.memory_types[i as usize]
+                .property_flags
+                .contains(properties);
+            if suitable && has_properties {
+                return Ok(i);
+            }
+        }
+        Err(VulkanError::NoSuitableMemoryType)
+    }
+
+    unsafe fn create_buffer(
+        &self,
+        size: vk::DeviceSize,
+        usage: vk::BufferUsageFlags,
+        properties: vk::MemoryPropertyFlags,
+    ) -> Result<(vk::Buffer, vk::DeviceMemory), VulkanError> {
+        let logical = self.logical.as_ref().unwrap();
+        let create_info = vk::BufferCreateInfo::default()
+            .size(size)
+            .usage(usage)
+            .sharing_mode(vk::SharingMode::EXCLUSIVE);
+        let buffer = logical.create_buffer(&create_info, None)?;
+
+        let requirements = logical.get_buffer_memory_requirements(buffer);
+        let memory_type = self.find_memory_type(requirements.memory_type_bits, properties)?;
+        let alloc_info = vk::MemoryAllocateInfo::default()
+            .allocation_size(requirements.size)
+            .memory_type_index(memory_type);
+        let memory = logical.allocate_memory(&alloc_info, None)?;
+        logical.bind_buffer_memory(buffer, memory, 0)?;
+        Ok((buffer, memory))
+    }
+
+    pub(crate) fn create_uniform_buffers(&self) -> Result<MappedBuffers, VulkanError> {
+        let logical = self.logical.as_ref().unwrap();
+        let size = size_of::<CameraUbo>() as vk::DeviceSize;
+        let mut buffers = Vec::with_capacity(MAX_FRAMES_IN_FLIGHT);
+        let mut memories = Vec::with_capacity(MAX_FRAMES_IN_FLIGHT);
+        let mut mapped = Vec::with_capacity(MAX_FRAMES_IN_FLIGHT);
+        for _ in 0..MAX_FRAMES_IN_FLIGHT {
+            let (buffer, memory) = unsafe {
+                self.create_buffer(
+                    size,
+                    vk::BufferUsageFlags::UNIFORM_BUFFER,
+                    vk::MemoryPropertyFlags::HOST_VISIBLE | vk::MemoryPropertyFlags::HOST_COHERENT,
+                )?
+            };
+            let ptr = unsafe { logical.map_memory(memory, 0, size, vk::MemoryMapFlags::empty())? };
+            buffers.push(buffer);
+            memories.push(memory);
+            mapped.push(ptr);
+        }
+        Ok((buffers, memories, mapped))
+    }
+
+    /// Builds the Vulkan clip-space projection matrix: `nalgebra_glm::perspective`
+    /// targets OpenGL's `-1..1` depth range and Y-up NDC, so we use the
+    /// zero-to-one variant for Vulkan's `0..1` depth range and flip Y ourselves.
+    fn projection(aspect_ratio: f32) -> glm::Mat4 {
+        let mut projection = glm::perspective_rh_zo(aspect_ratio, 45f32.to_radians(), 0.1, 1000.0);
+        projection[(1, 1)] *= -1.0;
+        projection
+    }
+
+    fn update_uniform_buffer(&self, view: &WindowView, camera: &Camera) {
+        let aspect_ratio = view.extent.width as f32 / view.extent.height as f32;
+        let ubo = CameraUbo {
+            model: glm::Mat4::identity(),
+            view: camera.view_matrix(),
+            projection: Self::projection(aspect_ratio),
+        };
+        let ptr = view.uniform_buffers_mapped[view.current_frame] as *mut CameraUbo;
+        unsafe { ptr.copy_from_nonoverlapping(&ubo, 1) };
+    }
+
+    // HUD overlay vertex buffer.
+
+    pub(crate) fn create_overlay_vertex_buffers(&self) -> Result<MappedBuffers, VulkanError> {
+        let logical = self.logical.as_ref().unwrap();
+        let size = (MAX_OVERLAY_VERTICES * size_of::<OverlayVertex>()) as vk::DeviceSize;
+        let mut buffers = Vec::with_capacity(MAX_FRAMES_IN_FLIGHT);
+        let mut memories = Vec::with_capacity(MAX_FRAMES_IN_FLIGHT);
+        let mut mapped = Vec::with_capacity(MAX_FRAMES_IN_FLIGHT);
+        for _ in 0..MAX_FRAMES_IN_FLIGHT {
+            let (buffer, memory) = unsafe {
+                self.create_buffer(
+                    size,
+                    vk::BufferUsageFlags::VERTEX_BUFFER,
+                    vk::MemoryPropertyFlags::HOST_VISIBLE | vk::MemoryPropertyFlags::HOST_COHERENT,
+                )?
+            };
+            let ptr = unsafe { logical.map_memory(memory, 0, size, vk::MemoryMapFlags::empty())? };
+            buffers.push(buffer);
+            memories.push(memory);
+            mapped.push(ptr);
+        }
+        Ok((buffers, memories, mapped))
+    }
+
+    /// Copies `vertices` into `view`'s overlay vertex buffer for the current
+    /// frame-in-flight, truncating to `MAX_OVERLAY_VERTICES` if the HUD
+    /// produced more (long frame-count or FPS readouts can), and returns the
+    /// number of vertices actually written.
+    fn update_overlay_vertex_buffer(&self, view: &WindowView, vertices: &[OverlayVertex]) -> u32 {
+        let count = vertices.len().min(MAX_OVERLAY_VERTICES);
+        let ptr = view.overlay_vertex_buffers_mapped[view.current_frame] as *mut OverlayVertex;
+        unsafe { ptr.copy_from_nonoverlapping(vertices.as_ptr(), count) };
+        count as u32
     }
 }
 
+pub struct SwapchainSetup {
+    surface: vk::SurfaceKHR,
+    swapchain: SwapchainKHR,
+    format: vk::Format,
+    extent: vk::Extent2D,
+    images: Vec<vk::Image>,
+    image_views: Vec<vk::ImageView>,
+}
+
 impl Drop for Device {
     fn drop(&mut self) {
         unsafe {
-            self.logical.destroy_device(None);
+            if let Some(logical) = &self.logical {
+                logical.destroy_device(None);
+            }
+            if let Some(debug_messenger) = &self.debug_messenger {
+                debug_messenger
+                    .loader
+                    .destroy_debug_utils_messenger(debug_messenger.messenger, None);
+            }
             self.instance.destroy_instance(None);
         }
     }
 }
+
+#[cfg(debug_assertions)]
+unsafe extern "system" fn vulkan_debug_callback(
+    _message_severity: vk::DebugUtilsMessageSeverityFlagsEXT,
+    _message_types: vk::DebugUtilsMessageTypeFlagsEXT,
+    callback_data: *const vk::DebugUtilsMessengerCallbackDataEXT,
+    _user_data: *mut std::os::raw::c_void,
+) -> vk::Bool32 {
+    let message = CStr::from_ptr((*callback_data).p_message).to_string_lossy();
+    eprintln!("[vulkan] {}", message);
+    vk::FALSE
+}